@@ -0,0 +1,168 @@
+//! Docker/OCI distribution Bearer token challenge handling.
+//!
+//! Registries such as Docker Hub, GHCR and quay.io reject anonymous
+//! requests with a `401 Unauthorized` carrying a `WWW-Authenticate: Bearer
+//! ...` header describing where to fetch a token. This module parses that
+//! challenge and caches the resulting tokens per scope so repeated calls
+//! against the same repository don't re-authenticate on every request.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A parsed `WWW-Authenticate: Bearer ...` challenge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BearerChallenge {
+    pub realm: String,
+    pub service: String,
+    pub scope: String,
+}
+
+/// Tokens are valid for 60 seconds by default when the registry does not
+/// specify `expires_in`, per the Docker token authentication spec.
+const DEFAULT_EXPIRES_IN: u64 = 60;
+
+/// Parse a `WWW-Authenticate` header value into its `Bearer` challenge
+/// parameters. Tolerates quoted and unquoted values, arbitrary parameter
+/// order, and a `scope` containing multiple space-separated scopes.
+pub(crate) fn parse_www_authenticate(header: &str) -> Option<BearerChallenge> {
+    let rest = header.trim().strip_prefix("Bearer")?.trim_start();
+
+    let mut realm = None;
+    let mut service = None;
+    let mut scope = None;
+
+    for part in split_params(rest) {
+        let (key, value) = part.split_once('=')?;
+        let value = value.trim().trim_matches('"');
+        match key.trim() {
+            "realm" => realm = Some(value.to_string()),
+            "service" => service = Some(value.to_string()),
+            "scope" => scope = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    Some(BearerChallenge {
+        realm: realm?,
+        service: service.unwrap_or_default(),
+        scope: scope.unwrap_or_default(),
+    })
+}
+
+/// Split `key=value` parameters on commas that are not inside a quoted
+/// value (a scope such as `scope="repository:a:pull,push"` must not be
+/// split on the inner comma).
+fn split_params(input: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut in_quotes = false;
+    let mut start = 0;
+
+    for (i, c) in input.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => {
+                parts.push(input[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    let last = input[start..].trim();
+    if !last.is_empty() {
+        parts.push(last);
+    }
+
+    parts
+}
+
+#[derive(Debug, Clone)]
+struct CachedToken {
+    token: String,
+    expires_at: Instant,
+}
+
+/// Caches Bearer tokens keyed by `service` + `scope`, so a scan across many
+/// tags/manifests for the same repository only authenticates once.
+#[derive(Debug, Default)]
+pub(crate) struct TokenCache {
+    tokens: Mutex<HashMap<String, CachedToken>>,
+}
+
+impl TokenCache {
+    pub(crate) fn new() -> Self {
+        TokenCache::default()
+    }
+
+    fn key(challenge: &BearerChallenge) -> String {
+        format!("{}|{}", challenge.service, challenge.scope)
+    }
+
+    pub(crate) fn get(&self, challenge: &BearerChallenge) -> Option<String> {
+        let tokens = self.tokens.lock().unwrap();
+        tokens.get(&Self::key(challenge)).and_then(|cached| {
+            if cached.expires_at > Instant::now() {
+                Some(cached.token.clone())
+            } else {
+                None
+            }
+        })
+    }
+
+    pub(crate) fn insert(&self, challenge: &BearerChallenge, token: String, expires_in: Option<u64>) {
+        let expires_in = expires_in.unwrap_or(DEFAULT_EXPIRES_IN);
+        let mut tokens = self.tokens.lock().unwrap();
+        tokens.insert(
+            Self::key(challenge),
+            CachedToken {
+                token,
+                expires_at: Instant::now() + Duration::from_secs(expires_in),
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_quoted_challenge() {
+        let header = r#"Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="repository:library/alpine:pull""#;
+        let challenge = parse_www_authenticate(header).unwrap();
+        assert_eq!(challenge.realm, "https://auth.docker.io/token");
+        assert_eq!(challenge.service, "registry.docker.io");
+        assert_eq!(challenge.scope, "repository:library/alpine:pull");
+    }
+
+    #[test]
+    fn parses_unquoted_and_multiple_scopes() {
+        let header = r#"Bearer realm=https://auth.example.com/token,service=registry,scope="repository:a:pull repository:b:pull,push""#;
+        let challenge = parse_www_authenticate(header).unwrap();
+        assert_eq!(challenge.realm, "https://auth.example.com/token");
+        assert_eq!(challenge.service, "registry");
+        assert_eq!(challenge.scope, "repository:a:pull repository:b:pull,push");
+    }
+
+    #[test]
+    fn rejects_non_bearer_challenge() {
+        assert!(parse_www_authenticate(r#"Basic realm="registry""#).is_none());
+    }
+
+    #[test]
+    fn caches_and_expires_tokens() {
+        let cache = TokenCache::new();
+        let challenge = BearerChallenge {
+            realm: "https://auth.example.com/token".to_string(),
+            service: "registry".to_string(),
+            scope: "repository:a:pull".to_string(),
+        };
+
+        assert!(cache.get(&challenge).is_none());
+        cache.insert(&challenge, "abc".to_string(), Some(3600));
+        assert_eq!(cache.get(&challenge).as_deref(), Some("abc"));
+
+        cache.insert(&challenge, "def".to_string(), Some(0));
+        assert!(cache.get(&challenge).is_none());
+    }
+}
@@ -0,0 +1,123 @@
+//! Content-digest verification for blobs downloaded from a registry.
+//!
+//! Registry blobs are referenced by an `<algorithm>:<hex>` digest (e.g.
+//! `sha256:abcd...`). Before trusting a downloaded blob we recompute its
+//! digest from the bytes actually received and compare it against the
+//! digest it was requested by, to guard against corrupt or tampered bytes
+//! from a proxy or mirror.
+
+use anyhow::anyhow;
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::AduanaError;
+
+enum Hasher {
+    Sha256(Sha256),
+    Sha512(Sha512),
+}
+
+impl Hasher {
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(hasher) => hasher.update(data),
+            Hasher::Sha512(hasher) => hasher.update(data),
+        }
+    }
+
+    fn finalize_hex(self) -> String {
+        match self {
+            Hasher::Sha256(hasher) => format!("{:x}", hasher.finalize()),
+            Hasher::Sha512(hasher) => format!("{:x}", hasher.finalize()),
+        }
+    }
+}
+
+/// An in-progress digest verification: feed it the downloaded bytes via
+/// [`DigestVerifier::update`] as they arrive, then check the result with
+/// [`DigestVerifier::verify`] once the body is exhausted.
+pub(crate) struct DigestVerifier {
+    hasher: Hasher,
+    algorithm: String,
+    expected_hex: String,
+}
+
+impl DigestVerifier {
+    /// Parse an `algorithm:hex` digest string. Supports `sha256` and `sha512`.
+    pub(crate) fn new(digest: &str) -> Result<Self, AduanaError> {
+        let (algorithm, hex) = digest
+            .split_once(':')
+            .ok_or_else(|| AduanaError::Runtime(anyhow!("malformed digest {:?}: expected algorithm:hex", digest)))?;
+        let algorithm = algorithm.to_ascii_lowercase();
+
+        let hasher = match algorithm.as_str() {
+            "sha256" => Hasher::Sha256(Sha256::new()),
+            "sha512" => Hasher::Sha512(Sha512::new()),
+            other => return Err(AduanaError::Runtime(anyhow!("unsupported digest algorithm {:?}", other))),
+        };
+
+        Ok(DigestVerifier {
+            hasher,
+            algorithm,
+            expected_hex: hex.to_ascii_lowercase(),
+        })
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    /// Finalize the hash and compare it against the expected digest,
+    /// normalizing case and the `algorithm:` prefix on both sides.
+    pub(crate) fn verify(self) -> Result<(), AduanaError> {
+        let algorithm = self.algorithm;
+        let expected = format!("{}:{}", algorithm, self.expected_hex);
+        let actual = format!("{}:{}", algorithm, self.hasher.finalize_hex());
+
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(AduanaError::DigestMismatch { expected, actual })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HELLO_SHA256: &str = "sha256:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    #[test]
+    fn verifies_matching_digest() {
+        let mut verifier = DigestVerifier::new(HELLO_SHA256).unwrap();
+        verifier.update(b"hello");
+        assert!(verifier.verify().is_ok());
+    }
+
+    #[test]
+    fn rejects_mismatched_digest() {
+        let mut verifier = DigestVerifier::new(
+            "sha256:0000000000000000000000000000000000000000000000000000000000000000",
+        )
+        .unwrap();
+        verifier.update(b"hello");
+        assert!(matches!(verifier.verify(), Err(AduanaError::DigestMismatch { .. })));
+    }
+
+    #[test]
+    fn normalizes_case_before_comparing() {
+        let mut verifier = DigestVerifier::new(&HELLO_SHA256.to_ascii_uppercase()).unwrap();
+        verifier.update(b"hello");
+        assert!(verifier.verify().is_ok());
+    }
+
+    #[test]
+    fn rejects_unsupported_algorithm() {
+        assert!(DigestVerifier::new("md5:d41d8cd98f00b204e9800998ecf8427e").is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_digest() {
+        assert!(DigestVerifier::new("not-a-digest").is_err());
+    }
+}
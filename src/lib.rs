@@ -28,20 +28,39 @@
 //! }
 //! ```
 
+mod auth;
+mod digest;
 mod registry;
 
 use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
 
 use anyhow::{anyhow, Context, Result};
-use reqwest::{Certificate, Client, header::ACCEPT};
+use futures::{stream, StreamExt};
+use reqwest::{
+    header::{HeaderName, ACCEPT, CONTENT_TYPE, LINK, WWW_AUTHENTICATE},
+    Certificate, Client, Method, Response, StatusCode, Url,
+};
 use thiserror::Error;
 
+use auth::{parse_www_authenticate, TokenCache};
+use digest::DigestVerifier;
 use registry::*;
 
 #[derive(Error, Debug)]
 pub enum AduanaError {
     #[error("Cannot connect to {url}: {reason}")]
     Connection { url: String, reason: String },
+    #[error("Failed to authenticate with {realm}: {reason}")]
+    Authentication { realm: String, reason: String },
+    #[error("Digest mismatch: expected {expected}, got {actual}")]
+    DigestMismatch { expected: String, actual: String },
+    #[error("Write operations are disabled; call AduanaInspector::with_deletes_enabled() to allow them")]
+    WriteDisabled,
+    #[error("{reference} not found on {name}")]
+    NotFound { name: String, reference: String },
+    #[error("Registry does not allow deleting {reference} from {name} (405 Method Not Allowed)")]
+    DeletesNotSupported { name: String, reference: String },
     #[error(transparent)]
     Runtime(#[from] anyhow::Error),
 }
@@ -86,9 +105,122 @@ pub struct ImageDetails {
     pub labels: HashMap<String, String>,
     pub arch: String,
     pub created: String,
+    pub layers: Vec<Layer>,
+    pub total_size: u64,
+    pub history: Vec<HistoryEntry>,
 }
 
-fn client(pem: &Option<Vec<u8>>) -> Result<Client, AduanaError> {
+/// One compressed layer making up an image, as listed in its manifest.
+#[derive(Debug, Clone)]
+pub struct Layer {
+    pub media_type: String,
+    pub size: u64,
+    pub digest: String,
+}
+
+impl From<ResponseLayer> for Layer {
+    fn from(layer: ResponseLayer) -> Self {
+        Layer {
+            media_type: layer.media_type,
+            size: layer.size,
+            digest: layer.digest,
+        }
+    }
+}
+
+/// One entry of the config blob's build history.
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub created: Option<String>,
+    pub created_by: Option<String>,
+    pub empty_layer: bool,
+}
+
+impl From<ResponseHistoryEntry> for HistoryEntry {
+    fn from(entry: ResponseHistoryEntry) -> Self {
+        HistoryEntry {
+            created: entry.created,
+            created_by: entry.created_by,
+            empty_layer: entry.empty_layer,
+        }
+    }
+}
+
+/// The platform (OS/architecture) a manifest in a manifest list or OCI
+/// image index targets.
+#[derive(Debug, Clone)]
+pub struct Platform {
+    pub os: String,
+    pub architecture: String,
+    pub variant: Option<String>,
+}
+
+/// One platform-specific entry of a manifest list / OCI image index.
+#[derive(Debug, Clone)]
+pub struct PlatformManifest {
+    pub digest: String,
+    pub platform: Platform,
+}
+
+impl From<ResponseManifestListEntry> for PlatformManifest {
+    fn from(entry: ResponseManifestListEntry) -> Self {
+        PlatformManifest {
+            digest: entry.digest,
+            platform: Platform {
+                os: entry.platform.os,
+                architecture: entry.platform.architecture,
+                variant: entry.platform.variant,
+            },
+        }
+    }
+}
+
+/// The media types accepted when requesting a manifest: a single-platform
+/// manifest (Docker or OCI) as well as a manifest list / OCI image index.
+const MANIFEST_ACCEPT: &str = "application/vnd.docker.distribution.manifest.v2+json, application/vnd.docker.distribution.manifest.list.v2+json, application/vnd.oci.image.manifest.v1+json, application/vnd.oci.image.index.v1+json";
+
+/// The header a registry returns the resolved manifest digest in.
+static DOCKER_CONTENT_DIGEST: HeaderName = HeaderName::from_static("docker-content-digest");
+
+/// Normalize a Rust `std::env::consts::ARCH`-style name to the arch string
+/// registries use in platform descriptors (e.g. `x86_64` -> `amd64`).
+fn normalize_arch(arch: &str) -> &str {
+    match arch {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        other => other,
+    }
+}
+
+/// Sum a manifest's total on-disk size: the config blob plus every layer.
+fn compute_total_size(config_size: u64, layers: &[ResponseLayer]) -> u64 {
+    config_size + layers.iter().map(|layer| layer.size).sum::<u64>()
+}
+
+/// Map a delete response's status code to a result, translating the
+/// registry's well-known failure statuses into [`AduanaError`] variants
+/// callers can match on.
+fn map_delete_status(status: StatusCode, name: &str, reference: &str) -> Result<(), AduanaError> {
+    match status {
+        status if status.is_success() => Ok(()),
+        StatusCode::METHOD_NOT_ALLOWED => Err(AduanaError::DeletesNotSupported {
+            name: name.to_string(),
+            reference: reference.to_string(),
+        }),
+        StatusCode::NOT_FOUND => Err(AduanaError::NotFound {
+            name: name.to_string(),
+            reference: reference.to_string(),
+        }),
+        status => Err(AduanaError::Runtime(anyhow!(
+            "unexpected status {} deleting {}:{}",
+            status,
+            name,
+            reference
+        ))),
+    }
+}
+
+fn build_client(pem: &Option<Vec<u8>>) -> Result<Client, AduanaError> {
     let mut builder = reqwest::Client::builder();
 
     if let Some(bytes) = pem {
@@ -97,8 +229,6 @@ fn client(pem: &Option<Vec<u8>>) -> Result<Client, AduanaError> {
     }
 
     let client = builder.build().with_context(||"Failed to build client!")?;
-    println!("Client: {:#?}", &client);
-
     Ok(client)
 }
 
@@ -113,24 +243,130 @@ impl<'a> AduanaImage<'a> {
         &self.tags
     }
 
-    /// Retrieve the image details for a specific tag.
+    /// Retrieve the image details for a specific tag. If the tag resolves
+    /// to a manifest list / OCI image index, the entry matching the host's
+    /// OS and architecture is picked automatically; use
+    /// [`AduanaImage::details_for_platform`] to choose explicitly, or
+    /// [`AduanaImage::platforms`] to see everything that is published.
     pub async fn details(&self, tag: &str) -> Result<ImageDetails, AduanaError> {
+        match self.fetch_manifest(tag).await? {
+            ResponseManifestOrList::Manifest(manifest) | ResponseManifestOrList::OciManifest(manifest) => {
+                self.build_details(tag, manifest).await
+            }
+            ResponseManifestOrList::ManifestList(list) | ResponseManifestOrList::Index(list) => {
+                let entry = Self::select_host_platform(&list.manifests).ok_or_else(|| {
+                    AduanaError::Runtime(anyhow!(
+                        "no manifest for host platform {}/{} in {}:{}",
+                        std::env::consts::OS,
+                        normalize_arch(std::env::consts::ARCH),
+                        &self.name,
+                        tag
+                    ))
+                })?;
+                self.details_for_digest(tag, &entry.digest).await
+            }
+        }
+    }
+
+    /// Retrieve the image details for the manifest matching `os`/`arch`
+    /// within a tag's manifest list / OCI image index. Returns an error if
+    /// the tag is not a manifest list, or has no entry for that platform.
+    pub async fn details_for_platform(
+        &self,
+        tag: &str,
+        os: &str,
+        arch: &str,
+    ) -> Result<ImageDetails, AduanaError> {
+        match self.fetch_manifest(tag).await? {
+            ResponseManifestOrList::Manifest(_) | ResponseManifestOrList::OciManifest(_) => {
+                Err(AduanaError::Runtime(anyhow!(
+                    "{}:{} is a single-platform manifest, not a manifest list",
+                    &self.name,
+                    tag
+                )))
+            }
+            ResponseManifestOrList::ManifestList(list) | ResponseManifestOrList::Index(list) => {
+                let entry = list
+                    .manifests
+                    .iter()
+                    .find(|entry| entry.platform.os == os && entry.platform.architecture == arch)
+                    .ok_or_else(|| {
+                        AduanaError::Runtime(anyhow!(
+                            "no manifest for platform {}/{} in {}:{}",
+                            os,
+                            arch,
+                            &self.name,
+                            tag
+                        ))
+                    })?;
+                self.details_for_digest(tag, &entry.digest).await
+            }
+        }
+    }
+
+    /// List the platform entries a tag's manifest list / OCI image index
+    /// publishes. Returns an empty list for a single-platform manifest.
+    pub async fn platforms(&self, tag: &str) -> Result<Vec<PlatformManifest>, AduanaError> {
+        match self.fetch_manifest(tag).await? {
+            ResponseManifestOrList::Manifest(_) | ResponseManifestOrList::OciManifest(_) => Ok(Vec::new()),
+            ResponseManifestOrList::ManifestList(list) | ResponseManifestOrList::Index(list) => {
+                Ok(list.manifests.into_iter().map(PlatformManifest::from).collect())
+            }
+        }
+    }
+
+    /// Pick the manifest list entry matching the host OS/architecture.
+    /// Returns `None` if the list has no entry for that exact platform, so
+    /// the caller never silently returns details for the wrong platform.
+    fn select_host_platform(manifests: &[ResponseManifestListEntry]) -> Option<&ResponseManifestListEntry> {
+        let os = std::env::consts::OS;
+        let arch = normalize_arch(std::env::consts::ARCH);
+        manifests
+            .iter()
+            .find(|entry| entry.platform.os == os && entry.platform.architecture == arch)
+    }
+
+    async fn fetch_manifest(&self, reference: &str) -> Result<ResponseManifestOrList, AduanaError> {
         let url = format!(
             "{}/v2/{}/manifests/{}",
-            &self.inspector.url, &self.name, tag
+            &self.inspector.url, &self.name, reference
         );
-        let client = client(&self.inspector.cert)?;
-        let response = client
-            .get(&url)
-            .header(
-                ACCEPT,
-                "application/vnd.docker.distribution.manifest.v2+json",
-            )
-            .send()
+        let client = self.inspector.client()?;
+        let response = self
+            .inspector
+            .send_with_auth(client, &url, Some(MANIFEST_ACCEPT))
             .await?;
-        let manifest: ResponseManifest = response.json().await?;
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.split(';').next().unwrap_or(value).trim().to_string());
+        let body = response.bytes().await?;
+        let manifest = ResponseManifestOrList::parse(content_type.as_deref(), &body)
+            .with_context(|| "Failed to parse manifest response")?;
+        Ok(manifest)
+    }
+
+    /// Fetch the single-platform manifest at `digest` and build its
+    /// details, keeping `tag` as the user-facing tag the caller asked for.
+    async fn details_for_digest(&self, tag: &str, digest: &str) -> Result<ImageDetails, AduanaError> {
+        match self.fetch_manifest(digest).await? {
+            ResponseManifestOrList::Manifest(manifest) | ResponseManifestOrList::OciManifest(manifest) => {
+                self.build_details(tag, manifest).await
+            }
+            ResponseManifestOrList::ManifestList(_) | ResponseManifestOrList::Index(_) => Err(
+                AduanaError::Runtime(anyhow!("manifest at digest {} is itself a manifest list", digest)),
+            ),
+        }
+    }
+
+    async fn build_details(&self, tag: &str, manifest: ResponseManifest) -> Result<ImageDetails, AduanaError> {
         let blob = self.retrieve_blob(&manifest.config.digest).await?;
 
+        let total_size = compute_total_size(manifest.config.size, &manifest.layers);
+        let layers = manifest.layers.into_iter().map(Layer::from).collect();
+        let history = blob.history.into_iter().map(HistoryEntry::from).collect();
+
         let result = ImageDetails {
             name: self.name.clone(),
             tag: tag.to_string(),
@@ -141,29 +377,134 @@ impl<'a> AduanaImage<'a> {
             labels: blob.config.labels,
             arch: blob.architecture,
             created: blob.created,
+            layers,
+            total_size,
+            history,
         };
 
         Ok(result)
     }
 
+    /// Download the config blob referenced by `digest`, verifying its
+    /// content digest as it streams in before trusting the bytes.
     async fn retrieve_blob(&self, digest: &str) -> Result<ResponseConfigBlob, AduanaError> {
         let url = format!("{}/v2/{}/blobs/{}", &self.inspector.url, &self.name, digest);
-        let client = client(&self.inspector.cert)?;
-        let response = client.get(&url).send().await?;
-        let details: ResponseConfigBlob = response.json().await?;
+        let client = self.inspector.client()?;
+        let response = self.inspector.send_with_auth(client, &url, None).await?;
+
+        let mut verifier = DigestVerifier::new(digest)?;
+        let mut body = Vec::new();
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            verifier.update(&chunk);
+            body.extend_from_slice(&chunk);
+        }
+        verifier.verify()?;
+
+        let details: ResponseConfigBlob =
+            serde_json::from_slice(&body).with_context(|| "Failed to parse config blob")?;
         Ok(details)
     }
+
+    /// Delete `tag` from the registry. Resolves the tag to its manifest
+    /// digest first, since the registry's delete endpoint only accepts
+    /// digests.
+    pub async fn delete_tag(&self, tag: &str) -> Result<(), AduanaError> {
+        let digest = self.resolve_digest(tag).await?;
+        self.delete_digest(&digest).await
+    }
+
+    /// Delete the manifest at `digest` from the registry.
+    pub async fn delete_digest(&self, digest: &str) -> Result<(), AduanaError> {
+        if !self.inspector.deletes_enabled {
+            return Err(AduanaError::WriteDisabled);
+        }
+
+        let url = format!("{}/v2/{}/manifests/{}", &self.inspector.url, &self.name, digest);
+        let client = self.inspector.client()?;
+        let response = self
+            .inspector
+            .send_method_with_auth(client, Method::DELETE, &url, None)
+            .await?;
+
+        map_delete_status(response.status(), &self.name, digest)
+    }
+
+    /// Resolve `tag` to its manifest digest via a `HEAD` request, reading
+    /// the `Docker-Content-Digest` response header.
+    async fn resolve_digest(&self, tag: &str) -> Result<String, AduanaError> {
+        let url = format!(
+            "{}/v2/{}/manifests/{}",
+            &self.inspector.url, &self.name, tag
+        );
+        let client = self.inspector.client()?;
+        let response = self
+            .inspector
+            .send_method_with_auth(client, Method::HEAD, &url, Some(MANIFEST_ACCEPT))
+            .await?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => {
+                return Err(AduanaError::NotFound {
+                    name: self.name.clone(),
+                    reference: tag.to_string(),
+                })
+            }
+            status if !status.is_success() => {
+                return Err(AduanaError::Runtime(anyhow!(
+                    "unexpected status {} resolving digest for {}:{}",
+                    status,
+                    &self.name,
+                    tag
+                )))
+            }
+            _ => {}
+        }
+
+        response
+            .headers()
+            .get(&DOCKER_CONTENT_DIGEST)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string())
+            .ok_or_else(|| {
+                AduanaError::Runtime(anyhow!(
+                    "registry did not return Docker-Content-Digest for {}:{}",
+                    &self.name,
+                    tag
+                ))
+            })
+    }
 }
 
+/// How many repositories to fan out `tags/list` requests for at once when
+/// no explicit concurrency is configured.
+const DEFAULT_CONCURRENCY: usize = 8;
+
 #[derive(Clone)]
 pub struct AduanaInspector {
     url: String,
     cert: Option<Vec<u8>>,
+    credentials: Option<(String, String)>,
+    tokens: Arc<TokenCache>,
+    page_size: Option<usize>,
+    concurrency: usize,
+    client_cell: Arc<OnceLock<Client>>,
+    deletes_enabled: bool,
 }
 
 impl std::fmt::Debug for AduanaInspector {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "AduanaInspector {{ url: {}, cert: {} }}", &self.url, self.cert.is_some())
+        write!(
+            f,
+            "AduanaInspector {{ url: {}, cert: {}, credentials: {}, page_size: {:?}, concurrency: {}, deletes_enabled: {} }}",
+            &self.url,
+            self.cert.is_some(),
+            self.credentials.is_some(),
+            self.page_size,
+            self.concurrency,
+            self.deletes_enabled
+        )
     }
 }
 
@@ -172,11 +513,49 @@ impl AduanaInspector {
         AduanaInspector {
             url: url.to_string(),
             cert: None,
+            credentials: None,
+            tokens: Arc::new(TokenCache::new()),
+            page_size: None,
+            concurrency: DEFAULT_CONCURRENCY,
+            client_cell: Arc::new(OnceLock::new()),
+            deletes_enabled: false,
         }
     }
 
     pub fn with_cert(mut self, pem: Vec<u8>) -> Self {
         self.cert = Some(pem);
+        // Force the client to be rebuilt with the new cert on next use.
+        self.client_cell = Arc::new(OnceLock::new());
+        self
+    }
+
+    /// Configure HTTP Basic credentials to send when a registry's token
+    /// endpoint requires authentication to mint a Bearer token.
+    pub fn with_credentials(mut self, user: &str, password: &str) -> Self {
+        self.credentials = Some((user.to_string(), password.to_string()));
+        self
+    }
+
+    /// Allow [`AduanaImage::delete_tag`] and [`AduanaImage::delete_digest`]
+    /// to issue `DELETE` requests. Off by default, since deleting a
+    /// manifest is destructive and most registries require auth for it
+    /// anyway.
+    pub fn with_deletes_enabled(mut self) -> Self {
+        self.deletes_enabled = true;
+        self
+    }
+
+    /// Request at most `page_size` repositories per `_catalog` page.
+    /// Without this, the registry applies its own default page size.
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    /// How many `tags/list` requests to run concurrently while listing
+    /// images. Defaults to `DEFAULT_CONCURRENCY`.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
         self
     }
 
@@ -185,34 +564,203 @@ impl AduanaInspector {
     }
 
     pub async fn images(&'_ self) -> Result<Vec<AduanaImage<'_>>, AduanaError> {
-        let url = format!("{}/v2/_catalog", &self.url);
-        let client = client(&self.cert)?;
-        let response = client.get(&url).send().await?;
+        let names = self.catalog().await?;
+
+        let images = stream::iter(names)
+            .map(|name| async move { self.retrieve_image(&name).await })
+            .buffer_unordered(self.concurrency)
+            .collect::<Vec<_>>()
+            .await;
+
+        images
+            .into_iter()
+            .map(|image| {
+                let image = image?;
+                Ok(AduanaImage {
+                    inspector: self,
+                    name: image.name,
+                    tags: image.tags,
+                })
+            })
+            .collect()
+    }
 
-        let mut images = Vec::new();
-        let catalog: ResponseCatalog = response
-            .json()
-            .await
-            .with_context(|| "Failed to parse catalog response")?;
-        for name in catalog.repositories {
-            let image = self.retrieve_image(&name).await?;
-            let image = AduanaImage {
-                inspector: self,
-                name: image.name,
-                tags: image.tags,
-            };
-            images.push(image);
+    /// Fetch the full repository catalog, following `Link: ...; rel="next"`
+    /// pagination until the registry stops returning a next page.
+    async fn catalog(&self) -> Result<Vec<String>, AduanaError> {
+        let mut repositories = Vec::new();
+        let mut last = None;
+
+        loop {
+            let url = self.catalog_url(last.as_deref())?;
+            let client = self.client()?;
+            let response = self.send_with_auth(client, &url, None).await?;
+
+            let next = response
+                .headers()
+                .get(LINK)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|header| self.parse_next_last(header));
+
+            let catalog: ResponseCatalog = response
+                .json()
+                .await
+                .with_context(|| "Failed to parse catalog response")?;
+            repositories.extend(catalog.repositories);
+
+            match next {
+                Some(value) => last = Some(value),
+                None => break,
+            }
         }
-        Ok(images)
+
+        Ok(repositories)
+    }
+
+    fn catalog_url(&self, last: Option<&str>) -> Result<String, AduanaError> {
+        let mut url = Url::parse(&format!("{}/v2/_catalog", &self.url)).map_err(|error| {
+            AduanaError::Connection {
+                url: "invalid".to_string(),
+                reason: format!("Invalid registry URL {}: {}", &self.url, error),
+            }
+        })?;
+        {
+            let mut pairs = url.query_pairs_mut();
+            if let Some(page_size) = self.page_size {
+                pairs.append_pair("n", &page_size.to_string());
+            }
+            if let Some(last) = last {
+                pairs.append_pair("last", last);
+            }
+        }
+        Ok(url.to_string())
+    }
+
+    /// Extract the `last` query parameter from a `Link: <...>; rel="next"`
+    /// header, resolving the link relative to the registry's base URL.
+    fn parse_next_last(&self, link_header: &str) -> Option<String> {
+        let base = Url::parse(&self.url).ok()?;
+        link_header.split(',').find_map(|part| {
+            let mut segments = part.split(';');
+            let target = segments.next()?.trim().trim_start_matches('<').trim_end_matches('>');
+            let is_next = segments.any(|rel| matches!(rel.trim(), "rel=\"next\"" | "rel=next"));
+            if !is_next {
+                return None;
+            }
+            let url = base.join(target).ok()?;
+            url.query_pairs()
+                .find(|(key, _)| key == "last")
+                .map(|(_, value)| value.into_owned())
+        })
     }
 
     async fn retrieve_image(&self, name: &str) -> Result<ResponseImage, AduanaError> {
         let url = format!("{}/v2/{}/tags/list", &self.url, name);
-        let client = client(&self.cert)?;
-        let response = client.get(&url).send().await?;
+        let client = self.client()?;
+        let response = self.send_with_auth(client, &url, None).await?;
         let image: ResponseImage = response.json().await?;
         Ok(image)
     }
+
+    /// Borrow the shared, lazily-built `reqwest::Client`. Built once per
+    /// inspector and reused across every request so TLS state and
+    /// connection pooling carry over between calls.
+    fn client(&self) -> Result<&Client, AduanaError> {
+        if let Some(client) = self.client_cell.get() {
+            return Ok(client);
+        }
+        let built = build_client(&self.cert)?;
+        Ok(self.client_cell.get_or_init(|| built))
+    }
+
+    /// Send a GET request, transparently handling the Docker/OCI Bearer
+    /// token challenge: if the registry answers `401` with a
+    /// `WWW-Authenticate: Bearer ...` header, fetch (or reuse a cached)
+    /// token for the challenge's scope and retry once with it attached.
+    async fn send_with_auth(
+        &self,
+        client: &Client,
+        url: &str,
+        accept: Option<&str>,
+    ) -> Result<Response, AduanaError> {
+        self.send_method_with_auth(client, Method::GET, url, accept).await
+    }
+
+    /// Like [`AduanaInspector::send_with_auth`], but for an arbitrary HTTP
+    /// method (used by `HEAD`/`DELETE` in the write API).
+    async fn send_method_with_auth(
+        &self,
+        client: &Client,
+        method: Method,
+        url: &str,
+        accept: Option<&str>,
+    ) -> Result<Response, AduanaError> {
+        let build = |token: Option<&str>| {
+            let mut builder = client.request(method.clone(), url);
+            if let Some(accept) = accept {
+                builder = builder.header(ACCEPT, accept);
+            }
+            if let Some(token) = token {
+                builder = builder.bearer_auth(token);
+            }
+            builder
+        };
+
+        let response = build(None).send().await?;
+        if response.status() != StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+
+        let challenge = response
+            .headers()
+            .get(WWW_AUTHENTICATE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_www_authenticate);
+
+        let Some(challenge) = challenge else {
+            return Ok(response);
+        };
+
+        let token = self.token_for(client, &challenge).await?;
+        let retried = build(Some(&token)).send().await?;
+        Ok(retried)
+    }
+
+    /// Resolve a Bearer token for `challenge`, using the cache when possible.
+    async fn token_for(&self, client: &Client, challenge: &auth::BearerChallenge) -> Result<String, AduanaError> {
+        if let Some(token) = self.tokens.get(challenge) {
+            return Ok(token);
+        }
+
+        let mut request = client.get(&challenge.realm).query(&[
+            ("service", challenge.service.as_str()),
+            ("scope", challenge.scope.as_str()),
+        ]);
+        if let Some((user, password)) = &self.credentials {
+            request = request.basic_auth(user, Some(password));
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(AduanaError::Authentication {
+                realm: challenge.realm.clone(),
+                reason: format!("token endpoint returned {}", response.status()),
+            });
+        }
+
+        let body: ResponseToken = response
+            .json()
+            .await
+            .with_context(|| "Failed to parse token response")?;
+        let expires_in = body.expires_in;
+        let token = body.token().ok_or_else(|| AduanaError::Authentication {
+            realm: challenge.realm.clone(),
+            reason: "token response had neither `token` nor `access_token`".to_string(),
+        })?;
+
+        self.tokens.insert(challenge, token.clone(), expires_in);
+        Ok(token)
+    }
 }
 
 #[cfg(test)]
@@ -230,6 +778,90 @@ mod tests {
         let _ = env_logger::builder().is_test(true).try_init();
     }
 
+    #[test]
+    fn parses_last_param_from_next_link() {
+        let inspector = AduanaInspector::new("http://localhost:5000");
+        let header = r#"</v2/_catalog?n=50&last=alpine>; rel="next""#;
+        assert_eq!(
+            inspector.parse_next_last(header),
+            Some("alpine".to_string())
+        );
+    }
+
+    #[test]
+    fn ignores_links_without_rel_next() {
+        let inspector = AduanaInspector::new("http://localhost:5000");
+        let header = r#"</v2/_catalog?n=50&last=alpine>; rel="prev""#;
+        assert_eq!(inspector.parse_next_last(header), None);
+    }
+
+    #[test]
+    fn parses_unquoted_rel_next() {
+        let inspector = AduanaInspector::new("http://localhost:5000");
+        let header = "</v2/_catalog?n=50&last=alpine>; rel=next";
+        assert_eq!(
+            inspector.parse_next_last(header),
+            Some("alpine".to_string())
+        );
+    }
+
+    #[test]
+    fn sums_config_and_layer_sizes() {
+        let layers = vec![
+            ResponseLayer {
+                media_type: "application/vnd.oci.image.layer.v1.tar+gzip".to_string(),
+                size: 100,
+                digest: "sha256:aaa".to_string(),
+            },
+            ResponseLayer {
+                media_type: "application/vnd.oci.image.layer.v1.tar+gzip".to_string(),
+                size: 250,
+                digest: "sha256:bbb".to_string(),
+            },
+        ];
+        assert_eq!(compute_total_size(10, &layers), 360);
+    }
+
+    #[test]
+    fn sums_config_size_with_no_layers() {
+        assert_eq!(compute_total_size(10, &[]), 10);
+    }
+
+    #[test]
+    fn maps_success_status_to_ok() {
+        assert!(map_delete_status(StatusCode::ACCEPTED, "alpine", "sha256:aaa").is_ok());
+    }
+
+    #[test]
+    fn maps_method_not_allowed_to_deletes_not_supported() {
+        match map_delete_status(StatusCode::METHOD_NOT_ALLOWED, "alpine", "sha256:aaa") {
+            Err(AduanaError::DeletesNotSupported { name, reference }) => {
+                assert_eq!(name, "alpine");
+                assert_eq!(reference, "sha256:aaa");
+            }
+            other => panic!("Unexpected result! {:#?}", other),
+        }
+    }
+
+    #[test]
+    fn maps_not_found_to_not_found() {
+        match map_delete_status(StatusCode::NOT_FOUND, "alpine", "sha256:aaa") {
+            Err(AduanaError::NotFound { name, reference }) => {
+                assert_eq!(name, "alpine");
+                assert_eq!(reference, "sha256:aaa");
+            }
+            other => panic!("Unexpected result! {:#?}", other),
+        }
+    }
+
+    #[test]
+    fn maps_unexpected_status_to_runtime_error() {
+        assert!(matches!(
+            map_delete_status(StatusCode::INTERNAL_SERVER_ERROR, "alpine", "sha256:aaa"),
+            Err(AduanaError::Runtime(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_images() {
         let inspector = AduanaInspector::new("http://localhost:5000");
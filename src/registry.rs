@@ -24,16 +24,101 @@ pub struct ResponseImage {
     pub tags: Vec<String>,
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseToken {
+    pub token: Option<String>,
+    pub access_token: Option<String>,
+    pub expires_in: Option<u64>,
+}
+
+impl ResponseToken {
+    /// The registry may return `token`, `access_token`, or both; prefer
+    /// `token` as it is the field the Docker spec documents.
+    pub fn token(self) -> Option<String> {
+        self.token.or(self.access_token)
+    }
+}
+
+/// A manifest or manifest list/index response.
+pub enum ResponseManifestOrList {
+    Manifest(ResponseManifest),
+    OciManifest(ResponseManifest),
+    ManifestList(ResponseManifestList),
+    Index(ResponseManifestList),
+}
+
+impl ResponseManifestOrList {
+    /// Parse a manifest/manifest-list response body, dispatching on the
+    /// registry's `Content-Type` response header rather than the body's own
+    /// `mediaType` field: buildkit and podman frequently publish OCI
+    /// manifests/indexes without a `mediaType`, and a registry's
+    /// `Content-Type` is authoritative regardless of what the body says. If
+    /// the header is missing or unrecognized, fall back to sniffing the
+    /// body for a `manifests` array rather than failing outright.
+    pub fn parse(content_type: Option<&str>, body: &[u8]) -> Result<Self, serde_json::Error> {
+        let is_oci = content_type.is_some_and(|value| value.contains("oci"));
+        let is_list = match content_type {
+            Some(value) if value.contains("manifest.list") || value.contains("image.index") => true,
+            Some(value) if value.contains("manifest.v2") || value.contains("image.manifest") => false,
+            _ => serde_json::from_slice::<serde_json::Value>(body)
+                .map(|value| value.get("manifests").is_some())
+                .unwrap_or(false),
+        };
+        match (is_list, is_oci) {
+            (true, true) => Ok(Self::Index(serde_json::from_slice(body)?)),
+            (true, false) => Ok(Self::ManifestList(serde_json::from_slice(body)?)),
+            (false, true) => Ok(Self::OciManifest(serde_json::from_slice(body)?)),
+            (false, false) => Ok(Self::Manifest(serde_json::from_slice(body)?)),
+        }
+    }
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ResponseManifest {
     pub config: ResponseConfig,
+    #[serde(default)]
+    pub layers: Vec<ResponseLayer>,
 }
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ResponseConfig {
     pub digest: String,
+    pub size: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseLayer {
+    pub media_type: String,
+    pub size: u64,
+    pub digest: String,
+}
+
+/// A manifest list (`manifest.list.v2+json`) or OCI image index
+/// (`image.index.v1+json`): one entry per platform the tag is published for.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseManifestList {
+    pub manifests: Vec<ResponseManifestListEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseManifestListEntry {
+    pub digest: String,
+    pub platform: ResponsePlatform,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponsePlatform {
+    pub os: String,
+    pub architecture: String,
+    #[serde(default)]
+    pub variant: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -42,6 +127,19 @@ pub struct ResponseConfigBlob {
     pub architecture: String,
     pub config: ConfigDetails,
     pub created: String,
+    #[serde(default)]
+    pub history: Vec<ResponseHistoryEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseHistoryEntry {
+    #[serde(default)]
+    pub created: Option<String>,
+    #[serde(default)]
+    pub created_by: Option<String>,
+    #[serde(default)]
+    pub empty_layer: bool,
 }
 
 #[derive(Default, Deserialize)]
@@ -53,4 +151,48 @@ pub struct ConfigDetails {
     pub working_dir: Option<String>,
     #[serde(deserialize_with = "deserialize_null_default")]
     pub labels: HashMap<String, String>,
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCKER_MANIFEST: &str = r#"{
+        "mediaType": "application/vnd.docker.distribution.manifest.v2+json",
+        "config": {"digest": "sha256:aaa", "size": 1234},
+        "layers": [{"mediaType": "application/vnd.docker.image.rootfs.diff.tar.gzip", "size": 100, "digest": "sha256:bbb"}]
+    }"#;
+
+    const OCI_INDEX_NO_MEDIA_TYPE: &str = r#"{
+        "manifests": [
+            {"digest": "sha256:aaa", "platform": {"os": "linux", "architecture": "amd64"}},
+            {"digest": "sha256:bbb", "platform": {"os": "unknown", "architecture": "unknown"}}
+        ]
+    }"#;
+
+    #[test]
+    fn dispatches_manifest_on_docker_content_type() {
+        let content_type = Some("application/vnd.docker.distribution.manifest.v2+json");
+        let parsed = ResponseManifestOrList::parse(content_type, DOCKER_MANIFEST.as_bytes()).unwrap();
+        assert!(matches!(parsed, ResponseManifestOrList::Manifest(_)));
+    }
+
+    #[test]
+    fn dispatches_index_on_oci_content_type_despite_missing_media_type() {
+        let content_type = Some("application/vnd.oci.image.index.v1+json");
+        let parsed = ResponseManifestOrList::parse(content_type, OCI_INDEX_NO_MEDIA_TYPE.as_bytes()).unwrap();
+        assert!(matches!(parsed, ResponseManifestOrList::Index(_)));
+    }
+
+    #[test]
+    fn sniffs_manifests_array_when_content_type_is_missing() {
+        let parsed = ResponseManifestOrList::parse(None, OCI_INDEX_NO_MEDIA_TYPE.as_bytes()).unwrap();
+        assert!(matches!(parsed, ResponseManifestOrList::ManifestList(_)));
+    }
+
+    #[test]
+    fn sniffs_single_manifest_when_content_type_is_unrecognized() {
+        let parsed =
+            ResponseManifestOrList::parse(Some("application/octet-stream"), DOCKER_MANIFEST.as_bytes()).unwrap();
+        assert!(matches!(parsed, ResponseManifestOrList::Manifest(_)));
+    }
+}